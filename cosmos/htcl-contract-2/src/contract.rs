@@ -4,12 +4,36 @@ use cosmwasm_std::{
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use sha2::{Digest, Sha256};
+use sha3::Keccak256;
 
 use crate::error::ContractError;
 use crate::msg::{
     AliceWithdrawnEvent, BobWithdrawnEvent, Cw20Withdrawal, ExecuteMsg, InstantiateMsg, QueryMsg,
 };
-use crate::state::{Config, CONFIG, CW20_BALANCES};
+use crate::state::{Config, HashAlgo, CONFIG, CW20_BALANCES};
+
+// Hashes `secret` with `algo` and formats the digest as lowercase hex, so it
+// can be compared directly against `Config.hashlock`
+fn hash_secret(algo: &HashAlgo, secret: &str) -> String {
+    match algo {
+        HashAlgo::Sha256 => {
+            let mut hasher = Sha256::new();
+            hasher.update(secret.as_bytes());
+            format!("{:x}", hasher.finalize())
+        }
+        HashAlgo::Keccak256 => {
+            // An EVM counterpart commits to `keccak256` of the raw secret
+            // bytes, not its UTF-8 encoding, so `secret` is the hex
+            // encoding of those bytes here rather than the preimage itself
+            let Ok(raw) = hex::decode(secret) else {
+                return String::new();
+            };
+            let mut hasher = Keccak256::new();
+            hasher.update(&raw);
+            format!("{:x}", hasher.finalize())
+        }
+    }
+}
 
 #[entry_point]
 pub fn instantiate(
@@ -39,6 +63,7 @@ pub fn instantiate(
         bob,
         timelock: msg.timelock,
         hashlock: msg.hashlock,
+        hash_algo: msg.hash_algo,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -93,9 +118,7 @@ pub fn execute_bob_withdraw(
     }
 
     // Validate secret
-    let mut hasher = Sha256::new();
-    hasher.update(secret.as_bytes());
-    let secret_hash = format!("{:x}", hasher.finalize());
+    let secret_hash = hash_secret(&config.hash_algo, &secret);
 
     if secret_hash != config.hashlock {
         return Err(ContractError::InvalidSecret {});
@@ -284,6 +307,7 @@ fn query_config(deps: Deps) -> StdResult<crate::msg::ConfigResponse> {
         bob: config.bob.to_string(),
         timelock: config.timelock,
         hashlock: config.hashlock,
+        hash_algo: config.hash_algo,
     })
 }
 
@@ -315,9 +339,7 @@ fn query_timelock_expired(deps: Deps, env: cosmwasm_std::Env) -> StdResult<bool>
 fn query_valid_secret(deps: Deps, secret: String) -> StdResult<bool> {
     let config = CONFIG.load(deps.storage)?;
 
-    let mut hasher = Sha256::new();
-    hasher.update(secret.as_bytes());
-    let secret_hash = format!("{:x}", hasher.finalize());
+    let secret_hash = hash_secret(&config.hash_algo, &secret);
 
     Ok(secret_hash == config.hashlock)
 }
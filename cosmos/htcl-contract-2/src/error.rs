@@ -0,0 +1,29 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid secret")]
+    InvalidSecret {},
+
+    #[error("Timelock has not expired yet")]
+    TimelockNotExpired {},
+
+    #[error("Timelock has already expired")]
+    TimelockExpired {},
+
+    #[error("Invalid timelock")]
+    InvalidTimelock {},
+
+    #[error("Invalid hashlock")]
+    InvalidHashlock {},
+
+    #[error("Invalid recipient address")]
+    InvalidRecipientAddress {},
+}
@@ -0,0 +1,33 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+// Hashing convention used to validate the revealed secret against
+// `Config.hashlock`. EVM-compatible counterpart chains commit to
+// `keccak256(secret)` rather than `sha256(secret)`.
+#[cw_serde]
+pub enum HashAlgo {
+    Sha256,
+    Keccak256,
+}
+
+#[cw_serde]
+pub struct Config {
+    pub alice: Addr,
+    pub bob: Addr,
+    pub timelock: u64,
+    pub hashlock: String,
+    pub hash_algo: HashAlgo,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const CW20_BALANCES: Map<&Addr, Uint128> = Map::new("cw20_balances");
+
+// Events
+#[cw_serde]
+pub struct HTCLCreatedEvent {
+    pub alice: String,
+    pub bob: String,
+    pub timelock: u64,
+    pub hashlock: String,
+}
@@ -1,15 +1,14 @@
-use cosmwasm_std::{
-    coins, Addr, BankMsg, BlockInfo, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response,
-    SubMsg, Timestamp, Uint128, WasmMsg,
-};
-use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
-use cw_multi_test::{App, Bank, Contract, ContractWrapper, Executor};
-
-use htcl_contract::contract::{execute, instantiate, query};
-use htcl_contract::error::ContractError;
-use htcl_contract::msg::{
-    BalanceResponse, ConfigResponse, ContractInfoResponse, ExecuteMsg, InstantiateMsg, QueryMsg,
-};
+// `htcl-contract` predates the multi-swap, id-keyed HTLC registry; that
+// functionality now lives in the `htcl` crate, so this suite exercises it
+// through `htcl`'s current `Create`/id-keyed message shapes instead.
+use cosmwasm_std::{coins, Addr, Binary, BlockInfo, Coin, Env, MessageInfo};
+use cw_multi_test::{App, Contract, ContractWrapper};
+use sha2::{Digest, Sha256};
+
+use htcl::contract::{execute, instantiate, query};
+use htcl::error::ContractError;
+use htcl::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use htcl::state::HashAlgo;
 
 fn mock_app() -> App {
     App::default()
@@ -24,7 +23,7 @@ fn mock_env(height: u64, time: u64) -> Env {
     Env {
         block: BlockInfo {
             height,
-            time: Timestamp::from_seconds(time),
+            time: cosmwasm_std::Timestamp::from_seconds(time),
             chain_id: "test".to_string(),
         },
         contract: cosmwasm_std::ContractInfo {
@@ -41,526 +40,335 @@ fn mock_info(sender: &str, funds: &[Coin]) -> MessageInfo {
     }
 }
 
-#[test]
-fn test_instantiate() {
-    let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
-
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
-    let hashlock = "a665a45920422f9d417e4867efdc4fb8a04a1f3fff1fa07e998e86f7f7a27ae3";
+fn sha256_hex(secret: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(secret.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
 
-    let msg = InstantiateMsg {
+#[allow(clippy::too_many_arguments)]
+fn create_msg(id: &str, bob: &str, timelock: u64, hashlock: &str) -> ExecuteMsg {
+    ExecuteMsg::Create {
+        id: id.to_string(),
         bob: bob.to_string(),
         timelock,
         hashlock: hashlock.to_string(),
-    };
+        hash_algo: HashAlgo::Sha256,
+        cw20: None,
+        native: Some("atom".to_string()),
+        wormhole_bridge: None,
+        emitter_chain: None,
+        emitter_address: None,
+        guardian_addresses: None,
+        ibc_channel: None,
+    }
+}
 
-    let info = mock_info("alice", &[]);
+fn instantiate_registry(app: &mut App) {
+    let info = mock_info("admin", &[]);
     let env = mock_env(1, 100);
-
-    let res = instantiate(
+    instantiate(
         app.deps_mut(),
         env,
         info,
-        msg,
+        InstantiateMsg {
+            prng_seed: Binary::from(b"seed".to_vec()),
+            admin: Some("admin".to_string()),
+        },
     )
     .unwrap();
-
-    assert_eq!(res.messages.len(), 0);
-    assert_eq!(res.attributes[0].key, "method");
-    assert_eq!(res.attributes[0].value, "instantiate");
 }
 
 #[test]
-fn test_instantiate_invalid_timelock() {
+fn test_create_invalid_timelock() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
-
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 50u64; // Past time
-    let hashlock = "a665a45920422f9d417e4867efdc4fb8a04a1f3fff1fa07e998e86f7f7a27ae3";
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.to_string(),
-    };
-
-    let info = mock_info("alice", &[]);
-    let env = mock_env(1, 100); // Current time > timelock
+    let hashlock = sha256_hex("123");
+    let info = mock_info("alice", &coins(100, "atom"));
+    let env = mock_env(2, 100);
 
-    let res = instantiate(
+    let res = execute(
         app.deps_mut(),
         env,
         info,
-        msg,
+        create_msg("swap-1", "bob", 50, &hashlock), // timelock in the past
     );
 
-    assert!(res.is_err());
     assert_eq!(res.unwrap_err(), ContractError::InvalidTimelock {});
 }
 
 #[test]
-fn test_instantiate_invalid_hashlock() {
+fn test_create_invalid_hashlock() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
-
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
-    let hashlock = ""; // Empty hashlock
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.to_string(),
-    };
-
-    let info = mock_info("alice", &[]);
-    let env = mock_env(1, 100);
+    let info = mock_info("alice", &coins(100, "atom"));
+    let env = mock_env(2, 100);
 
-    let res = instantiate(
+    let res = execute(
         app.deps_mut(),
         env,
         info,
-        msg,
+        create_msg("swap-1", "bob", 1000, ""), // empty hashlock
     );
 
-    assert!(res.is_err());
     assert_eq!(res.unwrap_err(), ContractError::InvalidHashlock {});
 }
 
 #[test]
-fn test_bob_withdraw_success() {
+fn test_create_duplicate_id() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
-
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
-    let secret = "123";
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(secret.as_bytes());
-    let hashlock = format!("{:x}", hasher.finalize());
-
-    // Instantiate contract
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.clone(),
-    };
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
+    let hashlock = sha256_hex("123");
     let info = mock_info("alice", &coins(100, "atom"));
-    let env = mock_env(1, 100);
-
-    let res = instantiate(
+    execute(
         app.deps_mut(),
-        env,
+        mock_env(2, 100),
         info,
-        msg,
+        create_msg("swap-1", "bob", 1000, &hashlock),
     )
     .unwrap();
 
-    // Bob withdraws with correct secret
-    let withdraw_msg = ExecuteMsg::BobWithdraw {
-        secret: secret.to_string(),
-    };
-
-    let info = mock_info("bob", &[]);
-    let env = mock_env(2, 200); // Before timelock
-
+    let info = mock_info("alice", &coins(100, "atom"));
     let res = execute(
         app.deps_mut(),
-        env,
+        mock_env(3, 100),
         info,
-        withdraw_msg,
+        create_msg("swap-1", "bob", 1000, &hashlock),
     );
 
-    assert!(res.is_ok());
-    let res = res.unwrap();
-    assert_eq!(res.messages.len(), 1); // Bank transfer message
+    assert_eq!(res.unwrap_err(), ContractError::DuplicateSwapId {});
 }
 
 #[test]
-fn test_bob_withdraw_wrong_secret() {
+fn test_bob_withdraw_success() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
     let secret = "123";
-    let wrong_secret = "456";
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(secret.as_bytes());
-    let hashlock = format!("{:x}", hasher.finalize());
-
-    // Instantiate contract
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.clone(),
-    };
+    let hashlock = sha256_hex(secret);
 
     let info = mock_info("alice", &coins(100, "atom"));
-    let env = mock_env(1, 100);
-
-    let res = instantiate(
+    execute(
         app.deps_mut(),
-        env,
+        mock_env(2, 100),
         info,
-        msg,
+        create_msg("swap-1", "bob", 1000, &hashlock),
     )
     .unwrap();
 
-    // Bob withdraws with wrong secret
     let withdraw_msg = ExecuteMsg::BobWithdraw {
-        secret: wrong_secret.to_string(),
+        id: "swap-1".to_string(),
+        secret: secret.to_string(),
     };
-
     let info = mock_info("bob", &[]);
-    let env = mock_env(2, 200); // Before timelock
+    let res = execute(app.deps_mut(), mock_env(3, 200), info, withdraw_msg).unwrap();
 
-    let res = execute(
+    assert_eq!(res.messages.len(), 1); // Bank transfer message
+}
+
+#[test]
+fn test_bob_withdraw_wrong_secret() {
+    let mut app = mock_app();
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
+
+    let hashlock = sha256_hex("123");
+    let info = mock_info("alice", &coins(100, "atom"));
+    execute(
         app.deps_mut(),
-        env,
+        mock_env(2, 100),
         info,
-        withdraw_msg,
-    );
+        create_msg("swap-1", "bob", 1000, &hashlock),
+    )
+    .unwrap();
+
+    let withdraw_msg = ExecuteMsg::BobWithdraw {
+        id: "swap-1".to_string(),
+        secret: "wrong".to_string(),
+    };
+    let info = mock_info("bob", &[]);
+    let res = execute(app.deps_mut(), mock_env(3, 200), info, withdraw_msg);
 
-    assert!(res.is_err());
     assert_eq!(res.unwrap_err(), ContractError::InvalidSecret {});
 }
 
 #[test]
 fn test_bob_withdraw_after_timelock() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
     let secret = "123";
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(secret.as_bytes());
-    let hashlock = format!("{:x}", hasher.finalize());
-
-    // Instantiate contract
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.clone(),
-    };
-
+    let hashlock = sha256_hex(secret);
     let info = mock_info("alice", &coins(100, "atom"));
-    let env = mock_env(1, 100);
-
-    let res = instantiate(
+    execute(
         app.deps_mut(),
-        env,
+        mock_env(2, 100),
         info,
-        msg,
+        create_msg("swap-1", "bob", 1000, &hashlock),
     )
     .unwrap();
 
-    // Bob withdraws after timelock
     let withdraw_msg = ExecuteMsg::BobWithdraw {
+        id: "swap-1".to_string(),
         secret: secret.to_string(),
     };
-
     let info = mock_info("bob", &[]);
-    let env = mock_env(2, 1100); // After timelock
-
-    let res = execute(
-        app.deps_mut(),
-        env,
-        info,
-        withdraw_msg,
-    );
+    let res = execute(app.deps_mut(), mock_env(3, 1100), info, withdraw_msg);
 
-    assert!(res.is_err());
     assert_eq!(res.unwrap_err(), ContractError::TimelockExpired {});
 }
 
 #[test]
 fn test_alice_withdraw_success() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
-
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
-    let secret = "123";
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(secret.as_bytes());
-    let hashlock = format!("{:x}", hasher.finalize());
-
-    // Instantiate contract
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.clone(),
-    };
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
+    let hashlock = sha256_hex("123");
     let info = mock_info("alice", &coins(100, "atom"));
-    let env = mock_env(1, 100);
-
-    let res = instantiate(
+    execute(
         app.deps_mut(),
-        env,
+        mock_env(2, 100),
         info,
-        msg,
+        create_msg("swap-1", "bob", 1000, &hashlock),
     )
     .unwrap();
 
-    // Alice withdraws after timelock
-    let withdraw_msg = ExecuteMsg::AliceWithdraw {};
-
+    let withdraw_msg = ExecuteMsg::AliceWithdraw {
+        id: "swap-1".to_string(),
+    };
     let info = mock_info("alice", &[]);
-    let env = mock_env(2, 1100); // After timelock
+    let res = execute(app.deps_mut(), mock_env(3, 1100), info, withdraw_msg).unwrap();
 
-    let res = execute(
-        app.deps_mut(),
-        env,
-        info,
-        withdraw_msg,
-    );
-
-    assert!(res.is_ok());
-    let res = res.unwrap();
     assert_eq!(res.messages.len(), 1); // Bank transfer message
 }
 
 #[test]
 fn test_alice_withdraw_before_timelock() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
-
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
-    let secret = "123";
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(secret.as_bytes());
-    let hashlock = format!("{:x}", hasher.finalize());
-
-    // Instantiate contract
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.clone(),
-    };
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
+    let hashlock = sha256_hex("123");
     let info = mock_info("alice", &coins(100, "atom"));
-    let env = mock_env(1, 100);
-
-    let res = instantiate(
+    execute(
         app.deps_mut(),
-        env,
+        mock_env(2, 100),
         info,
-        msg,
+        create_msg("swap-1", "bob", 1000, &hashlock),
     )
     .unwrap();
 
-    // Alice withdraws before timelock
-    let withdraw_msg = ExecuteMsg::AliceWithdraw {};
-
+    let withdraw_msg = ExecuteMsg::AliceWithdraw {
+        id: "swap-1".to_string(),
+    };
     let info = mock_info("alice", &[]);
-    let env = mock_env(2, 200); // Before timelock
-
-    let res = execute(
-        app.deps_mut(),
-        env,
-        info,
-        withdraw_msg,
-    );
+    let res = execute(app.deps_mut(), mock_env(3, 200), info, withdraw_msg);
 
-    assert!(res.is_err());
     assert_eq!(res.unwrap_err(), ContractError::TimelockNotExpired {});
 }
 
 #[test]
 fn test_unauthorized_withdrawal() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let charlie = Addr::unchecked("charlie");
-    let timelock = 1000u64;
     let secret = "123";
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(secret.as_bytes());
-    let hashlock = format!("{:x}", hasher.finalize());
-
-    // Instantiate contract
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.clone(),
-    };
-
+    let hashlock = sha256_hex(secret);
     let info = mock_info("alice", &coins(100, "atom"));
-    let env = mock_env(1, 100);
-
-    let res = instantiate(
+    execute(
         app.deps_mut(),
-        env,
+        mock_env(2, 100),
         info,
-        msg,
+        create_msg("swap-1", "bob", 1000, &hashlock),
     )
     .unwrap();
 
-    // Charlie tries to withdraw (unauthorized)
     let withdraw_msg = ExecuteMsg::BobWithdraw {
+        id: "swap-1".to_string(),
         secret: secret.to_string(),
     };
-
     let info = mock_info("charlie", &[]);
-    let env = mock_env(2, 200); // Before timelock
+    let res = execute(app.deps_mut(), mock_env(3, 200), info, withdraw_msg);
 
-    let res = execute(
-        app.deps_mut(),
-        env,
-        info,
-        withdraw_msg,
-    );
-
-    assert!(res.is_err());
     assert_eq!(res.unwrap_err(), ContractError::Unauthorized {});
 }
 
 #[test]
-fn test_query_config() {
+fn test_query_details_via_viewing_key() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
-
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
-    let hashlock = "a665a45920422f9d417e4867efdc4fb8a04a1f3fff1fa07e998e86f7f7a27ae3";
-
-    // Instantiate contract
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.to_string(),
-    };
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
+    let hashlock = sha256_hex("123");
     let info = mock_info("alice", &coins(100, "atom"));
-    let env = mock_env(1, 100);
-
-    let res = instantiate(
+    execute(
         app.deps_mut(),
-        env,
+        mock_env(2, 100),
         info,
-        msg,
+        create_msg("swap-1", "bob", 1000, &hashlock),
     )
     .unwrap();
 
-    // Query config
-    let query_msg = QueryMsg::GetConfig {};
-    let res = query(app.deps(), mock_env(1, 100), query_msg);
-    assert!(res.is_ok());
-
-    let config: ConfigResponse = serde_json::from_slice(&res.unwrap()).unwrap();
-    assert_eq!(config.alice, "alice");
-    assert_eq!(config.bob, "bob");
-    assert_eq!(config.timelock, 1000);
-    assert_eq!(config.hashlock, hashlock);
-}
-
-#[test]
-fn test_query_timelock_expired() {
-    let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
-
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
-    let hashlock = "a665a45920422f9d417e4867efdc4fb8a04a1f3fff1fa07e998e86f7f7a27ae3";
-
-    // Instantiate contract
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.to_string(),
-    };
-
-    let info = mock_info("alice", &coins(100, "atom"));
-    let env = mock_env(1, 100);
-
-    let res = instantiate(
+    execute(
         app.deps_mut(),
-        env,
-        info,
-        msg,
+        mock_env(2, 100),
+        mock_info("alice", &[]),
+        ExecuteMsg::SetViewingKey {
+            key: "alice-key".to_string(),
+        },
     )
     .unwrap();
 
-    // Query before timelock
-    let query_msg = QueryMsg::IsTimelockExpired {};
-    let res = query(app.deps(), mock_env(1, 200), query_msg);
-    assert!(res.is_ok());
-    let expired: bool = serde_json::from_slice(&res.unwrap()).unwrap();
-    assert_eq!(expired, false);
-
-    // Query after timelock
-    let res = query(app.deps(), mock_env(1, 1100), query_msg);
-    assert!(res.is_ok());
-    let expired: bool = serde_json::from_slice(&res.unwrap()).unwrap();
-    assert_eq!(expired, true);
+    let query_msg = QueryMsg::WithViewingKey {
+        address: "alice".to_string(),
+        viewing_key: "alice-key".to_string(),
+        query: htcl::msg::AuthenticatedQueryMsg::Details {
+            id: "swap-1".to_string(),
+        },
+    };
+    let res = query(app.deps(), mock_env(3, 100), query_msg).unwrap();
+    let details: ConfigResponse = serde_json::from_slice(&res).unwrap();
+
+    assert_eq!(details.alice, "alice");
+    assert_eq!(details.bob, "bob");
+    assert_eq!(details.timelock, 1000);
+    assert_eq!(details.hashlock, hashlock);
 }
 
 #[test]
-fn test_query_valid_secret() {
+fn test_query_timelock_expired() {
     let mut app = mock_app();
-    let contract_id = app.store_code(htcl_contract());
-
-    let alice = Addr::unchecked("alice");
-    let bob = Addr::unchecked("bob");
-    let timelock = 1000u64;
-    let secret = "123";
-    let mut hasher = sha2::Sha256::new();
-    hasher.update(secret.as_bytes());
-    let hashlock = format!("{:x}", hasher.finalize());
-
-    // Instantiate contract
-    let msg = InstantiateMsg {
-        bob: bob.to_string(),
-        timelock,
-        hashlock: hashlock.clone(),
-    };
+    app.store_code(htcl_contract());
+    instantiate_registry(&mut app);
 
+    let hashlock = sha256_hex("123");
     let info = mock_info("alice", &coins(100, "atom"));
-    let env = mock_env(1, 100);
-
-    let res = instantiate(
+    execute(
         app.deps_mut(),
-        env,
+        mock_env(2, 100),
         info,
-        msg,
+        create_msg("swap-1", "bob", 1000, &hashlock),
     )
     .unwrap();
 
-    // Query with correct secret
-    let query_msg = QueryMsg::IsValidSecret {
-        secret: secret.to_string(),
+    let query_msg = QueryMsg::IsTimelockExpired {
+        id: "swap-1".to_string(),
     };
-    let res = query(app.deps(), mock_env(1, 100), query_msg);
-    assert!(res.is_ok());
-    let valid: bool = serde_json::from_slice(&res.unwrap()).unwrap();
-    assert_eq!(valid, true);
+    let res = query(app.deps(), mock_env(3, 200), query_msg.clone()).unwrap();
+    let expired: bool = serde_json::from_slice(&res).unwrap();
+    assert!(!expired);
 
-    // Query with wrong secret
-    let query_msg = QueryMsg::IsValidSecret {
-        secret: "wrong".to_string(),
-    };
-    let res = query(app.deps(), mock_env(1, 100), query_msg);
-    assert!(res.is_ok());
-    let valid: bool = serde_json::from_slice(&res.unwrap()).unwrap();
-    assert_eq!(valid, false);
-} 
\ No newline at end of file
+    let res = query(app.deps(), mock_env(3, 1100), query_msg).unwrap();
+    let expired: bool = serde_json::from_slice(&res).unwrap();
+    assert!(expired);
+}
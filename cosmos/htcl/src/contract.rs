@@ -1,60 +1,350 @@
 use cosmwasm_std::{
-    attr, entry_point, to_binary, to_json_binary, Addr, BalanceResponse, BankQuery, Binary, Coin,
-    Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage, SubMsg, Uint128, WasmMsg,
+    attr, entry_point, from_json, to_binary, to_json_binary, Addr, BankMsg, Binary, Coin, Deps,
+    DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
 };
+#[cfg(feature = "ibc")]
+use cosmwasm_std::{IbcMsg, IbcTimeout, Timestamp};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use ripemd::Ripemd160;
 use sha2::{Digest, Sha256};
+use sha3::Keccak256;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    AuthenticatedQueryMsg, BalanceResponse, ConfigResponse, ContractInfoResponse,
+    CreateViewingKeyResponse, Cw20Balance, ExecuteMsg, InstantiateMsg, ListSwapsResponse,
+    MigrateMsg, Permit, QueryMsg, ReceiveMsg, TransactionHistoryResponse,
+};
 use crate::state::{
-    AliceWithdrawnEvent, BobWithdrawnEvent, Config, Cw20Withdrawal, CONFIG, CW20_BALANCES,
-    NATIVE_BALANCES,
+    AliceWithdrawnEvent, BobWithdrawnEvent, Config, ContractStatus, Cw20Withdrawal, HashAlgo,
+    HtclTx, HtclTxAction, WormholeSecretPayload, ADMIN, CONSUMED_VAAS, CONTRACT_STATUS,
+    CW20_BALANCES, NATIVE_BALANCES, PRNG_SEED, SWAPS, TX_COUNT, TX_HISTORY, VIEWING_KEYS,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+
+// Wormhole core-bridge ExecuteMsg, as published by `PostMessage`
+#[cosmwasm_schema::cw_serde]
+enum WormholeExecuteMsg {
+    PostMessage { message: Binary, nonce: u32 },
+}
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:htcl";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Hashes `secret` with `algo` and formats the digest as lowercase hex, so it
+// can be compared directly against `Config.hashlock`
+pub(crate) fn hash_secret(algo: &HashAlgo, secret: &str) -> String {
+    match algo {
+        HashAlgo::Sha256 => {
+            let mut hasher = Sha256::new();
+            hasher.update(secret.as_bytes());
+            format!("{:x}", hasher.finalize())
+        }
+        HashAlgo::Keccak256 => {
+            // An EVM counterpart commits to `keccak256` of the raw secret
+            // bytes, not its UTF-8 encoding, so `secret` is the hex
+            // encoding of those bytes here rather than the preimage itself
+            let Ok(raw) = hex::decode(secret) else {
+                return String::new();
+            };
+            let mut hasher = Keccak256::new();
+            hasher.update(&raw);
+            format!("{:x}", hasher.finalize())
+        }
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    info: MessageInfo,
+    _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    PRNG_SEED.save(deps.storage, &msg.prng_seed)?;
+
+    let admin = msg
+        .admin
+        .as_ref()
+        .map(|addr| deps.api.addr_validate(addr))
+        .transpose()?;
+    ADMIN.save(deps.storage, &admin)?;
+    CONTRACT_STATUS.save(deps.storage, &ContractStatus::Operational)?;
+
+    Ok(Response::new().add_attribute("method", "instantiate"))
+}
+
+// Every fund-moving entry point must pass this before touching escrowed
+// funds; `StopTransactions`/`StopAll` both forbid normal activity, and only
+// `ExecuteMsg::EmergencyRefund` is able to move funds under `StopAll`
+fn ensure_operational(deps: Deps) -> Result<(), ContractError> {
+    match CONTRACT_STATUS.load(deps.storage)? {
+        ContractStatus::Operational => Ok(()),
+        ContractStatus::StopTransactions | ContractStatus::StopAll => {
+            Err(ContractError::Frozen {})
+        }
+    }
+}
+
+fn ensure_admin(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    match ADMIN.load(deps.storage)? {
+        Some(admin) if admin == *sender => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+// Appends one entry to the contract-wide, append-only transaction log
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn record_tx(
+    deps: DepsMut,
+    env: &Env,
+    swap_id: String,
+    action: HtclTxAction,
+    counterparty: String,
+    secret: Option<String>,
+    native_amount: Vec<Coin>,
+    cw20_amount: Vec<Cw20Withdrawal>,
+) -> StdResult<()> {
+    let next_id = TX_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    TX_HISTORY.save(
+        deps.storage,
+        next_id,
+        &HtclTx {
+            swap_id,
+            action,
+            height: env.block.height,
+            timestamp: env.block.time.seconds(),
+            counterparty,
+            secret,
+            native_amount,
+            cw20_amount,
+        },
+    )?;
+    TX_COUNT.save(deps.storage, &(next_id + 1))?;
+    Ok(())
+}
+
+// Every entry point that releases a swap's escrowed funds (both withdrawal
+// handlers, VAA completion, emergency refund, and IBC's `release_to`) goes
+// through this so a cw20 with a reverting transfer hook, or a frozen
+// recipient, can't leave the swap decremented with nothing actually sent.
+// Each transfer clears its balance entry up front and is sent as a plain
+// `SubMsg::new`, which already aborts and reverts the whole tx (including
+// the balance decrements below) if the transfer fails, so no reply handling
+// or pending-transfer bookkeeping is needed to avoid a partial withdrawal.
+pub(crate) fn build_withdrawal_messages(
+    deps: DepsMut,
+    id: &str,
+    recipient: &Addr,
+) -> Result<(Vec<SubMsg>, Vec<Cw20Withdrawal>, Vec<Coin>), ContractError> {
+    let mut messages = Vec::new();
+    let mut cw20_withdrawals = Vec::new();
+    let mut coins = Vec::new();
+
+    let cw20_entries: Vec<(Addr, Uint128)> = CW20_BALANCES
+        .prefix(id.to_string())
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (token_addr, amount) in cw20_entries {
+        if amount.is_zero() {
+            continue;
+        }
+        CW20_BALANCES.remove(deps.storage, (id.to_string(), token_addr.clone()));
+        cw20_withdrawals.push(Cw20Withdrawal {
+            token: token_addr.to_string(),
+            amount,
+        });
+
+        let transfer_msg = Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        };
+        messages.push(SubMsg::new(WasmMsg::Execute {
+            contract_addr: token_addr.to_string(),
+            msg: to_json_binary(&transfer_msg)?,
+            funds: vec![],
+        }));
+    }
+
+    let native_entries: Vec<(String, Uint128)> = NATIVE_BALANCES
+        .prefix(id.to_string())
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (denom, amount) in native_entries {
+        if amount.is_zero() {
+            continue;
+        }
+        NATIVE_BALANCES.remove(deps.storage, (id.to_string(), denom.clone()));
+        let coin = Coin {
+            denom: denom.clone(),
+            amount,
+        };
+
+        messages.push(SubMsg::new(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin.clone()],
+        }));
+        coins.push(coin);
+    }
+
+    Ok((messages, cw20_withdrawals, coins))
+}
+
+pub fn execute_change_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_admin: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(deps.as_ref(), &info.sender)?;
+    let new_admin = new_admin
+        .as_ref()
+        .map(|addr| deps.api.addr_validate(addr))
+        .transpose()?;
+    ADMIN.save(deps.storage, &new_admin)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "change_admin")
+        .add_attribute(
+            "new_admin",
+            new_admin.map(|a| a.to_string()).unwrap_or_default(),
+        ))
+}
+
+pub fn execute_set_contract_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    level: ContractStatus,
+) -> Result<Response, ContractError> {
+    ensure_admin(deps.as_ref(), &info.sender)?;
+    CONTRACT_STATUS.save(deps.storage, &level)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_contract_status")
+        .add_attribute("level", format!("{level:?}")))
+}
+
+pub fn execute_emergency_refund(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(deps.as_ref(), &info.sender)?;
+    if CONTRACT_STATUS.load(deps.storage)? != ContractStatus::StopAll {
+        return Err(ContractError::Frozen {});
+    }
+
+    let config = SWAPS.load(deps.storage, id.clone())?;
+
+    let (messages, cw20_withdrawals, coins) =
+        build_withdrawal_messages(deps.branch(), &id, &config.alice)?;
+
+    record_tx(
+        deps,
+        &env,
+        id.clone(),
+        HtclTxAction::AliceWithdraw,
+        config.alice.to_string(),
+        None,
+        coins.clone(),
+        cw20_withdrawals.clone(),
+    )?;
+
+    let event = AliceWithdrawnEvent {
+        alice: config.alice.to_string(),
+        native_amount: coins,
+        cw20_amount: cw20_withdrawals,
+    };
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attribute("method", "emergency_refund")
+        .add_attribute("id", id)
+        .add_attribute("alice", config.alice)
+        .set_data(to_json_binary(&event)?))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+    bob: String,
+    timelock: u64,
+    hashlock: String,
+    hash_algo: HashAlgo,
+    cw20: Option<Addr>,
+    native: Option<String>,
+    wormhole_bridge: Option<String>,
+    emitter_chain: Option<u16>,
+    emitter_address: Option<Binary>,
+    guardian_addresses: Option<Vec<Binary>>,
+    ibc_channel: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_operational(deps.as_ref())?;
+
+    if SWAPS.has(deps.storage, id.clone()) {
+        return Err(ContractError::DuplicateSwapId {});
+    }
+
     // Validate bob address
-    let bob = deps.api.addr_validate(&msg.bob)?;
+    let bob = deps.api.addr_validate(&bob)?;
     if bob == info.sender {
         return Err(ContractError::InvalidRecipientAddress {});
     }
 
     // Validate timelock (must be in the future)
-    if msg.timelock <= _env.block.time.seconds() {
+    if timelock <= env.block.time.seconds() {
         return Err(ContractError::InvalidTimelock {});
     }
 
     // Validate hashlock (must not be empty)
-    if msg.hashlock.is_empty() {
+    if hashlock.is_empty() {
         return Err(ContractError::InvalidHashlock {});
     }
 
+    let wormhole_bridge = wormhole_bridge
+        .as_ref()
+        .map(|addr| deps.api.addr_validate(addr))
+        .transpose()?;
+
     let config = Config {
         alice: info.sender.clone(),
         bob,
-        timelock: msg.timelock,
-        hashlock: msg.hashlock,
-        cw20: msg.cw20.clone(),
-        native: msg.native.clone(),
+        timelock,
+        hashlock,
+        hash_algo,
+        cw20,
+        native,
+        wormhole_bridge,
+        emitter_chain,
+        emitter_address,
+        guardian_addresses,
+        ibc_channel,
     };
 
-    CONFIG.save(deps.storage, &config)?;
+    SWAPS.save(deps.storage, id.clone(), &config)?;
 
+    let deposited = info.funds.clone();
     for coin in info.funds {
-        NATIVE_BALANCES.save(deps.storage, coin.denom, &coin.amount)?;
+        NATIVE_BALANCES.save(deps.storage, (id.clone(), coin.denom), &coin.amount)?;
     }
 
+    record_tx(
+        deps.branch(),
+        &env,
+        id.clone(),
+        HtclTxAction::Create,
+        config.bob.to_string(),
+        None,
+        deposited,
+        vec![],
+    )?;
+
     // Emit event
     let event = crate::state::HTCLCreatedEvent {
         alice: info.sender.to_string(),
@@ -63,16 +353,14 @@ pub fn instantiate(
         hashlock: config.hashlock.clone(),
     };
 
-    let mut token_type = "".to_string();
-    if msg.cw20.is_some() {
-        token_type = msg.cw20.unwrap().to_string();
-    } else {
-        token_type = msg.native.unwrap();
-    }
+    let token_type = match &config.cw20 {
+        Some(addr) => addr.to_string(),
+        None => config.native.clone().unwrap_or_default(),
+    };
 
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     Ok(Response::new()
-        .add_attribute("method", "instantiate")
+        .add_attribute("method", "create")
+        .add_attribute("id", id)
         .add_attribute("alice", info.sender)
         .add_attribute("bob", config.bob)
         .add_attribute("timelock", config.timelock.to_string())
@@ -89,20 +377,106 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::BobWithdraw { secret } => execute_bob_withdraw(deps, env, info, secret),
-        ExecuteMsg::AliceWithdraw {} => execute_alice_withdraw(deps, env, info),
+        ExecuteMsg::Create {
+            id,
+            bob,
+            timelock,
+            hashlock,
+            hash_algo,
+            cw20,
+            native,
+            wormhole_bridge,
+            emitter_chain,
+            emitter_address,
+            guardian_addresses,
+            ibc_channel,
+        } => execute_create(
+            deps,
+            env,
+            info,
+            id,
+            bob,
+            timelock,
+            hashlock,
+            hash_algo,
+            cw20,
+            native,
+            wormhole_bridge,
+            emitter_chain,
+            emitter_address,
+            guardian_addresses,
+            ibc_channel,
+        ),
+        ExecuteMsg::BobWithdraw { id, secret } => execute_bob_withdraw(deps, env, info, id, secret),
+        ExecuteMsg::AliceWithdraw { id } => execute_alice_withdraw(deps, env, info, id),
         ExecuteMsg::Receive(msg) => execute_receive_cw20(deps, env, info, msg),
-        ExecuteMsg::DepositNative {} => execute_deposit_native(deps, env, info),
+        ExecuteMsg::DepositNative { id } => execute_deposit_native(deps, env, info, id),
+        ExecuteMsg::CompleteFromVaa { id, vaa } => {
+            execute_complete_from_vaa(deps, env, info, id, vaa)
+        }
+        ExecuteMsg::CreateViewingKey { entropy } => {
+            execute_create_viewing_key(deps, env, info, entropy)
+        }
+        ExecuteMsg::SetViewingKey { key } => execute_set_viewing_key(deps, info, key),
+        ExecuteMsg::ChangeAdmin { new_admin } => execute_change_admin(deps, info, new_admin),
+        ExecuteMsg::SetContractStatus { level } => {
+            execute_set_contract_status(deps, info, level)
+        }
+        ExecuteMsg::EmergencyRefund { id } => execute_emergency_refund(deps, env, info, id),
     }
 }
 
-pub fn execute_bob_withdraw(
+pub fn execute_create_viewing_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    entropy: String,
+) -> Result<Response, ContractError> {
+    let prng_seed = PRNG_SEED.load(deps.storage)?;
+    let mut hasher = Sha256::new();
+    hasher.update(prng_seed.as_slice());
+    hasher.update(entropy.as_bytes());
+    hasher.update(info.sender.as_bytes());
+    hasher.update(env.block.time.seconds().to_be_bytes());
+    let key = format!("{:x}", hasher.finalize());
+
+    save_viewing_key(deps, &info.sender, &key)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "create_viewing_key")
+        .set_data(to_json_binary(&CreateViewingKeyResponse { key })?))
+}
+
+pub fn execute_set_viewing_key(
     deps: DepsMut,
+    info: MessageInfo,
+    key: String,
+) -> Result<Response, ContractError> {
+    save_viewing_key(deps, &info.sender, &key)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_viewing_key")
+        .set_data(to_json_binary(&CreateViewingKeyResponse { key })?))
+}
+
+fn save_viewing_key(deps: DepsMut, address: &Addr, key: &str) -> Result<(), ContractError> {
+    let mut hasher = Sha256::new();
+    hasher.update(key.as_bytes());
+    let hashed: [u8; 32] = hasher.finalize().into();
+    VIEWING_KEYS.save(deps.storage, address.clone(), &hashed)?;
+    Ok(())
+}
+
+pub fn execute_bob_withdraw(
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    id: String,
     secret: String,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    ensure_operational(deps.as_ref())?;
+
+    let config = SWAPS.load(deps.storage, id.clone())?;
 
     // Check if caller is Bob
     if info.sender != config.bob {
@@ -115,65 +489,60 @@ pub fn execute_bob_withdraw(
     }
 
     // Validate secret
-    let mut hasher = Sha256::new();
-    hasher.update(secret.as_bytes());
-    let secret_hash = format!("{:x}", hasher.finalize());
+    let secret_hash = hash_secret(&config.hash_algo, &secret);
 
     if secret_hash != config.hashlock {
         return Err(ContractError::InvalidSecret {});
     }
 
-    // Get native balance
-    let mut native_coins = NATIVE_BALANCES.keys(deps.storage, None, None, Order::Ascending);
-
-    // Get cw20 balances
-    let mut cw20_withdrawals = Vec::new();
-    let mut cw20_messages = Vec::new();
-
-    for result in CW20_BALANCES.range(deps.storage, None, None, Order::Ascending) {
-        let (token_addr, amount) = result.unwrap();
-        if amount > Uint128::zero() {
-            cw20_withdrawals.push(Cw20Withdrawal {
-                token: token_addr.to_string(),
-                amount,
-            });
-
-            // Create transfer message
-            let transfer_msg = Cw20ExecuteMsg::Transfer {
-                recipient: config.bob.to_string(),
-                amount,
-            };
-
-            let wasm_msg = WasmMsg::Execute {
-                contract_addr: token_addr.to_string(),
-                msg: to_json_binary(&transfer_msg)?,
-                funds: vec![],
-            };
+    let (mut messages, cw20_withdrawals, coins) =
+        build_withdrawal_messages(deps.branch(), &id, &config.bob)?;
 
-            cw20_messages.push(SubMsg::new(wasm_msg));
-            // // Clear cw20 balances
-            // CW20_BALANCES.remove(deps.storage, token_addr);
-        }
+    // Publish the revealed secret to the Wormhole core bridge so the
+    // counterpart chain's HTLC can trustlessly complete from the VAA
+    if let Some(bridge) = &config.wormhole_bridge {
+        let payload = WormholeSecretPayload {
+            hashlock: config.hashlock.clone(),
+            secret: secret.clone(),
+            swap_recipient: config.bob.to_string(),
+        };
+        let publish_msg = WormholeExecuteMsg::PostMessage {
+            message: to_json_binary(&payload)?,
+            nonce: 0,
+        };
+        messages.push(SubMsg::new(WasmMsg::Execute {
+            contract_addr: bridge.to_string(),
+            msg: to_json_binary(&publish_msg)?,
+            funds: vec![],
+        }));
     }
 
-    // Create native transfer message
-    let mut coins = vec![];
-    let mut messages = cw20_messages;
-    while let Some(denom) = native_coins.next() {
-        let d = denom.unwrap();
-        let amount = NATIVE_BALANCES.load(deps.storage, d.clone())?;
-        let coin = Coin {
-            denom: d.clone(),
-            amount: amount,
+    // Notify the counterpart HTLC over IBC so it can settle natively, with
+    // the packet timing out at the same instant this swap's timelock expires
+    #[cfg(feature = "ibc")]
+    if let Some(channel_id) = &config.ibc_channel {
+        let packet = crate::ibc::IbcPacketPayload::RevealSecret {
+            id: id.clone(),
+            secret: secret.clone(),
         };
-        messages.push(SubMsg::new(cosmwasm_std::BankMsg::Send {
-            to_address: config.bob.to_string(),
-            amount: vec![coin.clone()],
+        messages.push(SubMsg::new(IbcMsg::SendPacket {
+            channel_id: channel_id.clone(),
+            data: to_json_binary(&packet)?,
+            timeout: IbcTimeout::with_timestamp(Timestamp::from_seconds(config.timelock)),
         }));
-        coins.push(coin);
-        // NATIVE_BALANCES.remove(deps.storage, d);
     }
 
+    record_tx(
+        deps,
+        &env,
+        id.clone(),
+        HtclTxAction::BobWithdraw,
+        config.bob.to_string(),
+        Some(secret.clone()),
+        coins.clone(),
+        cw20_withdrawals.clone(),
+    )?;
+
     // Emit event
     let event = BobWithdrawnEvent {
         bob: config.bob.to_string(),
@@ -185,16 +554,20 @@ pub fn execute_bob_withdraw(
     Ok(Response::new()
         .add_submessages(messages)
         .add_attribute("method", "bob_withdraw")
+        .add_attribute("id", id)
         .add_attribute("bob", config.bob)
-        .set_data(to_binary(&event)?))
+        .set_data(to_json_binary(&event)?))
 }
 
 pub fn execute_alice_withdraw(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    id: String,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    ensure_operational(deps.as_ref())?;
+
+    let config = SWAPS.load(deps.storage, id.clone())?;
 
     // Check if caller is Alice
     if info.sender != config.alice {
@@ -206,56 +579,19 @@ pub fn execute_alice_withdraw(
         return Err(ContractError::TimelockNotExpired {});
     }
 
-    // Get native balance
-    let mut native_coins = NATIVE_BALANCES.keys(deps.storage, None, None, Order::Ascending);
-
-    // Get cw20 balances
-    let mut cw20_withdrawals = Vec::new();
-    let mut cw20_messages = Vec::new();
-
-    for result in CW20_BALANCES.range(deps.storage, None, None, Order::Ascending) {
-        let (token_addr, amount) = result.unwrap();
-        if amount > Uint128::zero() {
-            cw20_withdrawals.push(Cw20Withdrawal {
-                token: token_addr.to_string(),
-                amount,
-            });
+    let (messages, cw20_withdrawals, coins) =
+        build_withdrawal_messages(deps.branch(), &id, &config.alice)?;
 
-            // Create transfer message
-            let transfer_msg = Cw20ExecuteMsg::Transfer {
-                recipient: config.bob.to_string(),
-                amount,
-            };
-
-            let wasm_msg = WasmMsg::Execute {
-                contract_addr: token_addr.to_string(),
-                msg: to_json_binary(&transfer_msg)?,
-                funds: vec![],
-            };
-
-            cw20_messages.push(SubMsg::new(wasm_msg));
-            // // Clear cw20 balances
-            // CW20_BALANCES.remove(deps.storage, token_addr);
-        }
-    }
-
-    // Create native transfer message
-    let mut coins = vec![];
-    let mut messages = cw20_messages;
-    while let Some(denom) = native_coins.next() {
-        let d = denom.unwrap();
-        let amount = NATIVE_BALANCES.load(deps.storage, d.clone())?;
-        let coin = Coin {
-            denom: d.clone(),
-            amount: amount,
-        };
-        messages.push(SubMsg::new(cosmwasm_std::BankMsg::Send {
-            to_address: config.bob.to_string(),
-            amount: vec![coin.clone()],
-        }));
-        coins.push(coin);
-        // NATIVE_BALANCES.remove(deps.storage, d);
-    }
+    record_tx(
+        deps,
+        &env,
+        id.clone(),
+        HtclTxAction::AliceWithdraw,
+        config.alice.to_string(),
+        None,
+        coins.clone(),
+        cw20_withdrawals.clone(),
+    )?;
 
     // Emit event
     let event = AliceWithdrawnEvent {
@@ -267,6 +603,7 @@ pub fn execute_alice_withdraw(
     Ok(Response::new()
         .add_submessages(messages)
         .add_attribute("method", "alice_withdraw")
+        .add_attribute("id", id)
         .add_attribute("alice", config.alice)
         .set_data(to_json_binary(&event)?))
 }
@@ -277,21 +614,32 @@ pub fn execute_receive_cw20(
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
+    ensure_operational(deps.as_ref())?;
+
+    let ReceiveMsg::TopUp { id } = from_json(&cw20_msg.msg)?;
+
+    // Swap must exist before it can be topped up
+    SWAPS.load(deps.storage, id.clone())?;
+
     // Store the cw20 tokens
     let token_addr = info.sender;
     let amount = cw20_msg.amount;
 
+    if amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+
     let current_balance = CW20_BALANCES
-        .load(deps.storage, token_addr.clone())
+        .load(deps.storage, (id.clone(), token_addr.clone()))
         .unwrap_or(Uint128::zero());
-    CW20_BALANCES.save(
-        deps.storage,
-        token_addr.clone(),
-        &(current_balance + amount),
-    )?;
+    let new_balance = current_balance
+        .checked_add(amount)
+        .map_err(|_| ContractError::BalanceOverflow {})?;
+    CW20_BALANCES.save(deps.storage, (id.clone(), token_addr.clone()), &new_balance)?;
 
     Ok(Response::new()
         .add_attribute("method", "receive_cw20")
+        .add_attribute("id", id)
         .add_attribute("token", token_addr)
         .add_attribute("amount", amount))
 }
@@ -300,16 +648,28 @@ pub fn execute_deposit_native(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
+    id: String,
 ) -> Result<Response, ContractError> {
-    let mut attrs = vec![attr("action", "deposit_native")];
+    ensure_operational(deps.as_ref())?;
+
+    // Swap must exist before it can be topped up
+    SWAPS.load(deps.storage, id.clone())?;
+
+    let mut attrs = vec![attr("action", "deposit_native"), attr("id", id.clone())];
 
     for coin in info.funds.iter() {
+        if coin.amount.is_zero() {
+            return Err(ContractError::InvalidAmount {});
+        }
+
         let existing = NATIVE_BALANCES
-            .may_load(deps.storage, coin.denom.clone())?
+            .may_load(deps.storage, (id.clone(), coin.denom.clone()))?
             .unwrap_or_default();
 
-        let new_total = existing + coin.amount;
-        NATIVE_BALANCES.save(deps.storage, coin.denom.clone(), &new_total)?;
+        let new_total = existing
+            .checked_add(coin.amount)
+            .map_err(|_| ContractError::BalanceOverflow {})?;
+        NATIVE_BALANCES.save(deps.storage, (id.clone(), coin.denom.clone()), &new_total)?;
 
         attrs.push(attr("denom", coin.denom.clone()));
         attrs.push(attr("amount", coin.amount.to_string()));
@@ -318,10 +678,1120 @@ pub fn execute_deposit_native(
     Ok(Response::new().add_attributes(attrs))
 }
 
+// Complete the swap using a signed VAA proving the secret was revealed by
+// the counterpart HTLC on another chain, instead of a local secret reveal.
+pub fn execute_complete_from_vaa(
+    mut deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    id: String,
+    vaa: Binary,
+) -> Result<Response, ContractError> {
+    ensure_operational(deps.as_ref())?;
+
+    let config = SWAPS.load(deps.storage, id.clone())?;
+
+    // Same asymmetry `execute_bob_withdraw` enforces: once the timelock
+    // expires, only Alice's refund may move the escrowed funds
+    if env.block.time.seconds() >= config.timelock {
+        return Err(ContractError::TimelockExpired {});
+    }
+
+    let parsed = parse_and_verify_vaa(deps.as_ref(), &config, &vaa)?;
+
+    let emitter_hex = parsed
+        .emitter_address
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    let replay_key = format!("{}:{}:{}", parsed.emitter_chain, emitter_hex, parsed.sequence);
+    if CONSUMED_VAAS.has(deps.storage, replay_key.clone()) {
+        return Err(ContractError::VaaAlreadyConsumed {});
+    }
+
+    let payload: WormholeSecretPayload = from_json(&parsed.payload)?;
+
+    let secret_hash = hash_secret(&config.hash_algo, &payload.secret);
+    if secret_hash != config.hashlock || payload.hashlock != config.hashlock {
+        return Err(ContractError::InvalidSecret {});
+    }
+
+    CONSUMED_VAAS.save(deps.storage, replay_key, &true)?;
+
+    // Release the escrowed funds to Bob, mirroring `execute_bob_withdraw`
+    let (messages, cw20_withdrawals, coins) =
+        build_withdrawal_messages(deps.branch(), &id, &config.bob)?;
+
+    record_tx(
+        deps,
+        &env,
+        id.clone(),
+        HtclTxAction::BobWithdraw,
+        config.bob.to_string(),
+        Some(payload.secret.clone()),
+        coins.clone(),
+        cw20_withdrawals.clone(),
+    )?;
+
+    let event = BobWithdrawnEvent {
+        bob: config.bob.to_string(),
+        secret: payload.secret,
+        native_amount: coins,
+        cw20_amount: cw20_withdrawals,
+    };
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attribute("method", "complete_from_vaa")
+        .add_attribute("id", id)
+        .add_attribute("bob", config.bob)
+        .set_data(to_json_binary(&event)?))
+}
+
+struct ParsedVaa {
+    emitter_chain: u16,
+    emitter_address: Vec<u8>,
+    sequence: u64,
+    payload: Vec<u8>,
+}
+
+// Parses and verifies a Wormhole VAA's standard layout:
+// version(1) | guardian_set_index(4) | len_signatures(1) | signatures[]
+// body: timestamp(4) | nonce(4) | emitter_chain(2) | emitter_address(32) |
+//       sequence(8) | consistency_level(1) | payload
+fn parse_and_verify_vaa(
+    deps: Deps,
+    config: &Config,
+    vaa: &Binary,
+) -> Result<ParsedVaa, ContractError> {
+    let guardian_addresses = config
+        .guardian_addresses
+        .as_ref()
+        .ok_or(ContractError::WormholeNotConfigured {})?;
+    let bytes = vaa.as_slice();
+
+    if bytes.len() < 6 {
+        return Err(ContractError::InvalidVaa {});
+    }
+    let _version = bytes[0];
+    let _guardian_set_index = u32::from_be_bytes(bytes[1..5].try_into().unwrap());
+    let num_signatures = bytes[5] as usize;
+
+    let sig_section_len = 6 + num_signatures * 66;
+    if bytes.len() < sig_section_len + 51 {
+        return Err(ContractError::InvalidVaa {});
+    }
+    let body = &bytes[sig_section_len..];
+
+    let mut hasher = Keccak256::new();
+    hasher.update(body);
+    let body_hash = hasher.finalize();
+    let mut hasher2 = Keccak256::new();
+    hasher2.update(body_hash);
+    let digest = hasher2.finalize();
+
+    let quorum = (guardian_addresses.len() * 2) / 3 + 1;
+    let mut verified = 0usize;
+    let mut last_guardian_index: Option<usize> = None;
+    for i in 0..num_signatures {
+        let offset = 6 + i * 66;
+        let guardian_index = bytes[offset] as usize;
+        let signature = &bytes[offset + 1..offset + 65];
+        let recovery_id = bytes[offset + 65];
+
+        // Upstream Wormhole requires guardian indices to be strictly
+        // increasing, so the same guardian's signature can't be repeated to
+        // fake quorum out of a single real signer
+        if last_guardian_index.is_some_and(|last| guardian_index <= last) {
+            return Err(ContractError::GuardianVerificationFailed {});
+        }
+        last_guardian_index = Some(guardian_index);
+
+        let recovered = deps
+            .api
+            .secp256k1_recover_pubkey(&digest, signature, recovery_id)
+            .map_err(|_| ContractError::GuardianVerificationFailed {})?;
+
+        let mut hasher3 = Keccak256::new();
+        hasher3.update(&recovered[1..]);
+        let pubkey_hash = hasher3.finalize();
+        let recovered_address = &pubkey_hash[12..];
+
+        if let Some(expected) = guardian_addresses.get(guardian_index) {
+            if expected.as_slice() == recovered_address {
+                verified += 1;
+            }
+        }
+    }
+    if verified < quorum {
+        return Err(ContractError::GuardianVerificationFailed {});
+    }
+
+    let emitter_chain = u16::from_be_bytes(body[8..10].try_into().unwrap());
+    let emitter_address = body[10..42].to_vec();
+    let sequence = u64::from_be_bytes(body[42..50].try_into().unwrap());
+    let payload = body[51..].to_vec();
+
+    if Some(emitter_chain) != config.emitter_chain
+        || config
+            .emitter_address
+            .as_ref()
+            .map(|a| a.as_slice() != emitter_address.as_slice())
+            .unwrap_or(true)
+    {
+        return Err(ContractError::UnauthorizedEmitter {});
+    }
+
+    Ok(ParsedVaa {
+        emitter_chain,
+        emitter_address,
+        sequence,
+        payload,
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(_deps: Deps, _env: Env, _msg: QueryMsg) -> StdResult<Binary> {
-    unimplemented!()
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidMigrationContract {});
+    }
+    if cmp_versions(&stored.version, CONTRACT_VERSION) == std::cmp::Ordering::Greater {
+        return Err(ContractError::CannotMigrateToOlderVersion {});
+    }
+
+    // Older versions tracked a single swap's native funds purely via bank
+    // queries instead of NATIVE_BALANCES; backfill it for that one legacy
+    // swap so `GetBalance`/`GetContractInfo` stay accurate going forward.
+    let ids: Vec<String> = SWAPS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    if let [legacy_id] = ids.as_slice() {
+        let has_native_entry = NATIVE_BALANCES
+            .prefix(legacy_id.clone())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        if !has_native_entry {
+            for coin in deps.querier.query_all_balances(&env.contract.address)? {
+                NATIVE_BALANCES.save(
+                    deps.storage,
+                    (legacy_id.clone(), coin.denom.clone()),
+                    &coin.amount,
+                )?;
+            }
+        }
+    }
+
+    // Contracts deployed before the killswitch existed have neither item set
+    if CONTRACT_STATUS.may_load(deps.storage)?.is_none() {
+        CONTRACT_STATUS.save(deps.storage, &ContractStatus::Operational)?;
+    }
+    if ADMIN.may_load(deps.storage)?.is_none() {
+        ADMIN.save(deps.storage, &None)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+// Compares two `major.minor.patch` version strings
+fn cmp_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |v: &str| -> Vec<u64> { v.split('.').map(|p| p.parse().unwrap_or(0)).collect() };
+    parse(a).cmp(&parse(b))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::GetBalance { id } => to_binary(&query_balance(deps, id)?),
+        QueryMsg::IsTimelockExpired { id } => to_binary(&query_timelock_expired(deps, env, id)?),
+        QueryMsg::ListSwaps { start_after, limit } => {
+            to_binary(&query_list_swaps(deps, start_after, limit)?)
+        }
+        QueryMsg::TransactionHistory { page, page_size } => {
+            to_binary(&query_transaction_history(deps, page, page_size)?)
+        }
+        QueryMsg::WithPermit { permit, query } => {
+            let caller = authenticate_with_permit(deps, &permit, &query)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            dispatch_authenticated_query(deps, env, query, &caller)
+        }
+        QueryMsg::WithViewingKey {
+            address,
+            viewing_key,
+            query,
+        } => {
+            let caller = authenticate_with_viewing_key(deps, &address, &viewing_key)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            dispatch_authenticated_query(deps, env, query, &caller)
+        }
+    }
+}
+
+// Query name used to scope a `Permit` to the sensitive queries it may answer
+fn authenticated_query_key(query: &AuthenticatedQueryMsg) -> &'static str {
+    match query {
+        AuthenticatedQueryMsg::Details { .. } => "details",
+        AuthenticatedQueryMsg::IsValidSecret { .. } => "is_valid_secret",
+        AuthenticatedQueryMsg::GetContractInfo { .. } => "get_contract_info",
+    }
+}
+
+fn authenticated_query_swap_id(query: &AuthenticatedQueryMsg) -> &str {
+    match query {
+        AuthenticatedQueryMsg::Details { id } => id,
+        AuthenticatedQueryMsg::IsValidSecret { id, .. } => id,
+        AuthenticatedQueryMsg::GetContractInfo { id } => id,
+    }
+}
+
+fn dispatch_authenticated_query(
+    deps: Deps,
+    _env: Env,
+    query: AuthenticatedQueryMsg,
+    caller: &Addr,
+) -> StdResult<Binary> {
+    let id = authenticated_query_swap_id(&query).to_string();
+    let config = SWAPS.load(deps.storage, id.clone())?;
+    if *caller != config.alice && *caller != config.bob {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    match query {
+        AuthenticatedQueryMsg::Details { id } => to_binary(&query_details(deps, id)?),
+        AuthenticatedQueryMsg::IsValidSecret { id, secret } => {
+            to_binary(&query_valid_secret(deps, id, secret)?)
+        }
+        AuthenticatedQueryMsg::GetContractInfo { id } => {
+            to_binary(&query_contract_info(deps, id)?)
+        }
+    }
+}
+
+// Verifies `permit.signature` over `permit.params` and that `query` is one
+// of the names the permit was scoped to, then derives a caller identity from
+// the recovered pubkey the same way a Cosmos secp256k1 canonical address is
+// derived (ripemd160(sha256(pubkey))) and matches it against the swap's
+// alice/bob canonical address bytes, to stand in for full bech32 address
+// recovery, which `cosmwasm_std::Api` does not expose
+fn authenticate_with_permit(
+    deps: Deps,
+    permit: &Permit,
+    query: &AuthenticatedQueryMsg,
+) -> Result<Addr, ContractError> {
+    let query_key = authenticated_query_key(query);
+    if !permit
+        .params
+        .allowed_queries
+        .iter()
+        .any(|q| q == query_key)
+    {
+        return Err(ContractError::PermitQueryNotAllowed {});
+    }
+
+    let sign_bytes = to_json_binary(&permit.params)?;
+    let mut hasher = Sha256::new();
+    hasher.update(sign_bytes.as_slice());
+    let digest = hasher.finalize();
+
+    let verified = deps
+        .api
+        .secp256k1_verify(
+            &digest,
+            permit.signature.signature.as_slice(),
+            permit.signature.pub_key.as_slice(),
+        )
+        .map_err(|_| ContractError::InvalidPermitSignature {})?;
+    if !verified {
+        return Err(ContractError::InvalidPermitSignature {});
+    }
+
+    let id = authenticated_query_swap_id(query);
+    let config = SWAPS.load(deps.storage, id.to_string())?;
+
+    let sha256_digest = Sha256::digest(permit.signature.pub_key.as_slice());
+    let identity = Ripemd160::digest(sha256_digest);
+
+    for candidate in [&config.alice, &config.bob] {
+        if deps.api.addr_canonicalize(candidate.as_str())?.as_slice() == identity.as_slice() {
+            return Ok(candidate.clone());
+        }
+    }
+    Err(ContractError::Unauthorized {})
+}
+
+fn authenticate_with_viewing_key(
+    deps: Deps,
+    address: &str,
+    viewing_key: &str,
+) -> Result<Addr, ContractError> {
+    let addr = deps.api.addr_validate(address)?;
+    let stored = VIEWING_KEYS
+        .may_load(deps.storage, addr.clone())?
+        .ok_or(ContractError::InvalidViewingKey {})?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(viewing_key.as_bytes());
+    let hashed: [u8; 32] = hasher.finalize().into();
+
+    if hashed != stored {
+        return Err(ContractError::InvalidViewingKey {});
+    }
+    Ok(addr)
+}
+
+fn query_details(deps: Deps, id: String) -> StdResult<ConfigResponse> {
+    let config = SWAPS.load(deps.storage, id)?;
+    Ok(ConfigResponse {
+        alice: config.alice.to_string(),
+        bob: config.bob.to_string(),
+        timelock: config.timelock,
+        hashlock: config.hashlock,
+        hash_algo: config.hash_algo,
+        cw20: config.cw20,
+        native: config.native,
+    })
+}
+
+fn swap_cw20_balances(deps: Deps, id: &str) -> StdResult<Vec<Cw20Balance>> {
+    let mut cw20_balances = Vec::new();
+    for result in CW20_BALANCES
+        .prefix(id.to_string())
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (token_addr, amount) = result?;
+        if amount > Uint128::zero() {
+            cw20_balances.push(Cw20Balance {
+                address: token_addr.to_string(),
+                amount,
+            });
+        }
+    }
+    Ok(cw20_balances)
+}
+
+fn swap_native_balances(deps: Deps, id: &str) -> StdResult<Vec<Coin>> {
+    let mut native_balances = Vec::new();
+    for result in NATIVE_BALANCES
+        .prefix(id.to_string())
+        .range(deps.storage, None, None, Order::Ascending)
+    {
+        let (denom, amount) = result?;
+        if amount > Uint128::zero() {
+            native_balances.push(Coin { denom, amount });
+        }
+    }
+    Ok(native_balances)
+}
+
+fn query_balance(deps: Deps, id: String) -> StdResult<BalanceResponse> {
+    Ok(BalanceResponse {
+        native: swap_native_balances(deps, &id)?,
+        cw20: swap_cw20_balances(deps, &id)?,
+    })
+}
+
+fn query_timelock_expired(deps: Deps, env: Env, id: String) -> StdResult<bool> {
+    let config = SWAPS.load(deps.storage, id)?;
+    Ok(env.block.time.seconds() >= config.timelock)
+}
+
+fn query_valid_secret(deps: Deps, id: String, secret: String) -> StdResult<bool> {
+    let config = SWAPS.load(deps.storage, id)?;
+
+    let secret_hash = hash_secret(&config.hash_algo, &secret);
+
+    Ok(secret_hash == config.hashlock)
+}
+
+fn query_contract_info(deps: Deps, id: String) -> StdResult<ContractInfoResponse> {
+    let config = SWAPS.load(deps.storage, id.clone())?;
+
+    Ok(ContractInfoResponse {
+        alice: config.alice.to_string(),
+        bob: config.bob.to_string(),
+        timelock: config.timelock,
+        hashlock: config.hashlock,
+        hash_algo: config.hash_algo,
+        cw20: config.cw20,
+        native: config.native,
+        native_balance: swap_native_balances(deps, &id)?,
+        cw20_balances: swap_cw20_balances(deps, &id)?,
+    })
+}
+
+// Default / max page sizes mirror cw20-escrow's `ListAccounts` pagination
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+fn query_list_swaps(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListSwapsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let swaps = SWAPS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListSwapsResponse { swaps })
+}
+
+fn query_transaction_history(
+    deps: Deps,
+    page: u32,
+    page_size: u32,
+) -> StdResult<TransactionHistoryResponse> {
+    let total = TX_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    let page_size = (page_size.max(1)) as u64;
+    let skip = page as u64 * page_size;
+
+    let mut txs = Vec::new();
+    if skip < total {
+        let mut idx = total - 1 - skip;
+        loop {
+            if let Some(tx) = TX_HISTORY.may_load(deps.storage, idx)? {
+                txs.push(tx);
+            }
+            if txs.len() as u64 >= page_size || idx == 0 {
+                break;
+            }
+            idx -= 1;
+        }
+    }
+
+    Ok(TransactionHistoryResponse { txs, total })
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{coins, OwnedDeps};
+    use crate::msg::{PermitParams, PermitSignature};
+
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    fn setup() -> (TestDeps, Env) {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            InstantiateMsg {
+                prng_seed: Binary::from(b"seed".to_vec()),
+                admin: Some("admin".to_string()),
+            },
+        )
+        .unwrap();
+        (deps, env)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_swap(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        id: &str,
+        bob: &str,
+        timelock: u64,
+        hashlock: &str,
+        hash_algo: HashAlgo,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            env,
+            info,
+            ExecuteMsg::Create {
+                id: id.to_string(),
+                bob: bob.to_string(),
+                timelock,
+                hashlock: hashlock.to_string(),
+                hash_algo,
+                cw20: None,
+                native: Some("uusd".to_string()),
+                wormhole_bridge: None,
+                emitter_chain: None,
+                emitter_address: None,
+                guardian_addresses: None,
+                ibc_channel: None,
+            },
+        )
+    }
+
+    fn sha256_hex(secret: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(secret.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    #[test]
+    fn create_and_bob_withdraw_success() {
+        let (mut deps, env) = setup();
+        let secret = "open-sesame";
+        let hashlock = sha256_hex(secret);
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &coins(100, "uusd")),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &hashlock,
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("bob", &[]),
+            ExecuteMsg::BobWithdraw {
+                id: "swap-1".to_string(),
+                secret: secret.to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    #[test]
+    fn create_duplicate_id_rejected() {
+        let (mut deps, env) = setup();
+        let hashlock = sha256_hex("s");
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &hashlock,
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let err = create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &hashlock,
+            HashAlgo::Sha256,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::DuplicateSwapId {});
+    }
+
+    #[test]
+    fn create_invalid_timelock_rejected() {
+        let (mut deps, env) = setup();
+        let err = create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds(),
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidTimelock {});
+    }
+
+    #[test]
+    fn create_invalid_hashlock_rejected() {
+        let (mut deps, env) = setup();
+        let err = create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            "",
+            HashAlgo::Sha256,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidHashlock {});
+    }
+
+    #[test]
+    fn bob_withdraw_wrong_secret_rejected() {
+        let (mut deps, env) = setup();
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &sha256_hex("right"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("bob", &[]),
+            ExecuteMsg::BobWithdraw {
+                id: "swap-1".to_string(),
+                secret: "wrong".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidSecret {});
+    }
+
+    #[test]
+    fn bob_withdraw_unauthorized_rejected() {
+        let (mut deps, env) = setup();
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("mallory", &[]),
+            ExecuteMsg::BobWithdraw {
+                id: "swap-1".to_string(),
+                secret: "s".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+
+    #[test]
+    fn bob_withdraw_after_timelock_rejected() {
+        let (mut deps, env) = setup();
+        let timelock = env.block.time.seconds() + 1000;
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            timelock,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let mut late_env = env;
+        late_env.block.time = late_env.block.time.plus_seconds(2000);
+        let err = execute(
+            deps.as_mut(),
+            late_env,
+            mock_info("bob", &[]),
+            ExecuteMsg::BobWithdraw {
+                id: "swap-1".to_string(),
+                secret: "s".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TimelockExpired {});
+    }
+
+    #[test]
+    fn alice_withdraw_before_timelock_rejected() {
+        let (mut deps, env) = setup();
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("alice", &[]),
+            ExecuteMsg::AliceWithdraw {
+                id: "swap-1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::TimelockNotExpired {});
+    }
+
+    #[test]
+    fn alice_withdraw_after_timelock_success() {
+        let (mut deps, env) = setup();
+        let timelock = env.block.time.seconds() + 1000;
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &coins(50, "uusd")),
+            "swap-1",
+            "bob",
+            timelock,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let mut late_env = env;
+        late_env.block.time = late_env.block.time.plus_seconds(2000);
+        let res = execute(
+            deps.as_mut(),
+            late_env,
+            mock_info("alice", &[]),
+            ExecuteMsg::AliceWithdraw {
+                id: "swap-1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    // EVM counterparts commit to `keccak256` of the raw secret bytes, so the
+    // on-chain hashlock/secret here are the hex encodings of those bytes
+    #[test]
+    fn keccak256_hash_algo_interops_with_raw_secret_bytes() {
+        let (mut deps, env) = setup();
+        let secret_bytes = b"cross-chain-secret";
+        let secret_hex = hex::encode(secret_bytes);
+        let mut hasher = Keccak256::new();
+        hasher.update(secret_bytes);
+        let hashlock = format!("{:x}", hasher.finalize());
+
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &hashlock,
+            HashAlgo::Keccak256,
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("bob", &[]),
+            ExecuteMsg::BobWithdraw {
+                id: "swap-1".to_string(),
+                secret: secret_hex,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn deposit_native_rejects_balance_overflow() {
+        let (mut deps, env) = setup();
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &coins(Uint128::MAX.u128(), "uusd")),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("alice", &coins(1, "uusd")),
+            ExecuteMsg::DepositNative {
+                id: "swap-1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::BalanceOverflow {});
+    }
+
+    #[test]
+    fn viewing_key_authenticates_and_rejects_wrong_key() {
+        let (mut deps, env) = setup();
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::SetViewingKey {
+                key: "my-key".to_string(),
+            },
+        )
+        .unwrap();
+
+        let ok = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::WithViewingKey {
+                address: "alice".to_string(),
+                viewing_key: "my-key".to_string(),
+                query: AuthenticatedQueryMsg::Details {
+                    id: "swap-1".to_string(),
+                },
+            },
+        );
+        assert!(ok.is_ok());
+
+        let err = query(
+            deps.as_ref(),
+            env,
+            QueryMsg::WithViewingKey {
+                address: "alice".to_string(),
+                viewing_key: "wrong-key".to_string(),
+                query: AuthenticatedQueryMsg::Details {
+                    id: "swap-1".to_string(),
+                },
+            },
+        );
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn permit_rejects_query_outside_allowed_scope() {
+        let (mut deps, env) = setup();
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let permit = Permit {
+            params: PermitParams {
+                permit_name: "test".to_string(),
+                chain_id: "test-1".to_string(),
+                allowed_queries: vec!["details".to_string()],
+            },
+            signature: PermitSignature {
+                pub_key: Binary::from(vec![2u8; 33]),
+                signature: Binary::from(vec![0u8; 64]),
+            },
+        };
+
+        let err = authenticate_with_permit(
+            deps.as_ref(),
+            &permit,
+            &AuthenticatedQueryMsg::IsValidSecret {
+                id: "swap-1".to_string(),
+                secret: "s".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::PermitQueryNotAllowed {});
+    }
+
+    #[test]
+    fn permit_rejects_invalid_signature() {
+        let (mut deps, env) = setup();
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        let permit = Permit {
+            params: PermitParams {
+                permit_name: "test".to_string(),
+                chain_id: "test-1".to_string(),
+                allowed_queries: vec!["is_valid_secret".to_string()],
+            },
+            signature: PermitSignature {
+                pub_key: Binary::from(vec![2u8; 33]),
+                signature: Binary::from(vec![0u8; 64]),
+            },
+        };
+
+        let err = authenticate_with_permit(
+            deps.as_ref(),
+            &permit,
+            &AuthenticatedQueryMsg::IsValidSecret {
+                id: "swap-1".to_string(),
+                secret: "s".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidPermitSignature {});
+    }
+
+    #[test]
+    fn frozen_contract_blocks_bob_withdraw() {
+        let (mut deps, env) = setup();
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::SetContractStatus {
+                level: ContractStatus::StopTransactions,
+            },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("bob", &[]),
+            ExecuteMsg::BobWithdraw {
+                id: "swap-1".to_string(),
+                secret: "s".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Frozen {});
+    }
+
+    #[test]
+    fn emergency_refund_requires_stop_all() {
+        let (mut deps, env) = setup();
+        create_swap(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &coins(10, "uusd")),
+            "swap-1",
+            "bob",
+            env.block.time.seconds() + 1000,
+            &sha256_hex("s"),
+            HashAlgo::Sha256,
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::SetContractStatus {
+                level: ContractStatus::StopTransactions,
+            },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::EmergencyRefund {
+                id: "swap-1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Frozen {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("admin", &[]),
+            ExecuteMsg::SetContractStatus {
+                level: ContractStatus::StopAll,
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("admin", &[]),
+            ExecuteMsg::EmergencyRefund {
+                id: "swap-1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+    }
+
+    fn dummy_vaa_config() -> Config {
+        Config {
+            alice: Addr::unchecked("alice"),
+            bob: Addr::unchecked("bob"),
+            timelock: 0,
+            hashlock: "abc".to_string(),
+            hash_algo: HashAlgo::Sha256,
+            cw20: None,
+            native: None,
+            wormhole_bridge: None,
+            emitter_chain: Some(1),
+            emitter_address: Some(Binary::from(vec![1u8; 32])),
+            guardian_addresses: Some(vec![Binary::from(vec![0u8; 20])]),
+            ibc_channel: None,
+        }
+    }
+
+    #[test]
+    fn vaa_too_short_is_rejected() {
+        let (deps, _env) = setup();
+        let config = dummy_vaa_config();
+        let err = parse_and_verify_vaa(deps.as_ref(), &config, &Binary::from(vec![0u8; 3]))
+            .unwrap_err();
+        assert_eq!(err, ContractError::InvalidVaa {});
+    }
+
+    #[test]
+    fn vaa_with_no_signatures_fails_quorum() {
+        let (deps, _env) = setup();
+        let config = dummy_vaa_config();
+        // num_signatures = 0, so only the 51-byte body is required
+        let bytes = vec![0u8; 6 + 51];
+        let err = parse_and_verify_vaa(deps.as_ref(), &config, &Binary::from(bytes)).unwrap_err();
+        assert_eq!(err, ContractError::GuardianVerificationFailed {});
+    }
+}
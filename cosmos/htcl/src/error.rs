@@ -38,4 +38,49 @@ pub enum ContractError {
 
     #[error("Invalid recipient address")]
     InvalidRecipientAddress {},
+
+    #[error("Wormhole bridge is not configured for this swap")]
+    WormholeNotConfigured {},
+
+    #[error("Invalid VAA")]
+    InvalidVaa {},
+
+    #[error("VAA guardian signature verification failed")]
+    GuardianVerificationFailed {},
+
+    #[error("VAA emitter is not the configured counterpart contract")]
+    UnauthorizedEmitter {},
+
+    #[error("VAA has already been consumed")]
+    VaaAlreadyConsumed {},
+
+    #[error("Swap id already exists")]
+    DuplicateSwapId {},
+
+    #[error("Cannot migrate from a different contract")]
+    InvalidMigrationContract {},
+
+    #[error("Cannot migrate to an older contract version")]
+    CannotMigrateToOlderVersion {},
+
+    #[error("Balance overflow")]
+    BalanceOverflow {},
+
+    #[error("IBC channel is not the configured counterpart channel for this swap")]
+    UnauthorizedIbcChannel {},
+
+    #[error("No IBC channel configured for this swap")]
+    IbcChannelNotConfigured {},
+
+    #[error("Permit signature is invalid")]
+    InvalidPermitSignature {},
+
+    #[error("Permit does not authorize this query")]
+    PermitQueryNotAllowed {},
+
+    #[error("Invalid viewing key")]
+    InvalidViewingKey {},
+
+    #[error("Contract is frozen")]
+    Frozen {},
 }
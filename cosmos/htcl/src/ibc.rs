@@ -0,0 +1,230 @@
+#![cfg(feature = "ibc")]
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    entry_point, from_json, to_json_binary, DepsMut, Env, IbcBasicResponse, IbcChannel,
+    IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcOrder,
+    IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, StdError,
+    StdResult, SubMsg,
+};
+use crate::contract::{build_withdrawal_messages, hash_secret, record_tx};
+use crate::error::ContractError;
+use crate::state::{ContractStatus, HtclTxAction, CONTRACT_STATUS, SWAPS};
+
+// Channel version pinned by both sides of the handshake; bump if the packet
+// payload below ever changes shape
+pub const IBC_VERSION: &str = "htcl-1";
+
+// Packet data exchanged between two HTLC instances over an established
+// channel so a swap can settle without a relayed VAA
+#[cw_serde]
+pub enum IbcPacketPayload {
+    // Bob revealed `secret` locally; the counterpart chain can now release
+    // its side of the swap to its Bob straight from the packet instead of
+    // waiting for `BobWithdraw` to be called there
+    RevealSecret { id: String, secret: String },
+    // The counterpart chain's Alice already refunded her leg after timelock
+    // expiry; mirror that refund here too instead of waiting for a timeout
+    ClaimRefund { id: String },
+}
+
+// Acknowledgement written back to the packet sender
+#[cw_serde]
+pub enum IbcPacketAck {
+    Ok {},
+    Error(String),
+}
+
+fn enforce_order_and_version(
+    channel: &IbcChannel,
+    counterparty_version: Option<&str>,
+) -> StdResult<()> {
+    if channel.order != IbcOrder::Unordered {
+        return Err(StdError::generic_err(
+            "htcl IBC channels must be unordered",
+        ));
+    }
+    if channel.version != IBC_VERSION {
+        return Err(StdError::generic_err(format!(
+            "must set channel version to `{IBC_VERSION}`"
+        )));
+    }
+    if let Some(counterparty_version) = counterparty_version {
+        if counterparty_version != IBC_VERSION {
+            return Err(StdError::generic_err(format!(
+                "counterparty must set channel version to `{IBC_VERSION}`"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> StdResult<IbcChannelOpenResponse> {
+    enforce_order_and_version(msg.channel(), msg.counterparty_version())?;
+    Ok(None)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> StdResult<IbcBasicResponse> {
+    enforce_order_and_version(msg.channel(), msg.counterparty_version())?;
+    Ok(IbcBasicResponse::new()
+        .add_attribute("method", "ibc_channel_connect")
+        .add_attribute("channel_id", &msg.channel().endpoint.channel_id))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> StdResult<IbcBasicResponse> {
+    Ok(IbcBasicResponse::new()
+        .add_attribute("method", "ibc_channel_close")
+        .add_attribute("channel_id", &msg.channel().endpoint.channel_id))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> StdResult<IbcReceiveResponse> {
+    let channel_id = msg.packet.dest.channel_id.clone();
+    let handled = (|| -> Result<(String, Vec<SubMsg>), ContractError> {
+        let payload: IbcPacketPayload = from_json(&msg.packet.data)?;
+        match payload {
+            IbcPacketPayload::RevealSecret { id, secret } => {
+                release_to(deps, &env, &channel_id, &id, secret, true)
+            }
+            IbcPacketPayload::ClaimRefund { id } => {
+                release_to(deps, &env, &channel_id, &id, String::new(), false)
+            }
+        }
+    })();
+
+    match handled {
+        Ok((method, messages)) => Ok(IbcReceiveResponse::new()
+            .set_ack(to_json_binary(&IbcPacketAck::Ok {})?)
+            .add_submessages(messages)
+            .add_attribute("method", method)),
+        Err(err) => Ok(IbcReceiveResponse::new()
+            .set_ack(to_json_binary(&IbcPacketAck::Error(err.to_string()))?)
+            .add_attribute("method", "ibc_packet_receive")
+            .add_attribute("error", err.to_string())),
+    }
+}
+
+// Shared release logic for both packet kinds: `to_bob` releases to Bob after
+// validating the revealed secret, otherwise it refunds Alice the way
+// `execute_alice_withdraw` would, gated on the swap's own timelock. Records
+// the release in the transaction history the same way `execute_bob_withdraw`/
+// `execute_alice_withdraw` do, so IBC-settled swaps aren't missing from the
+// lifecycle log.
+fn release_to(
+    mut deps: DepsMut,
+    env: &Env,
+    channel_id: &str,
+    id: &str,
+    secret: String,
+    to_bob: bool,
+) -> Result<(String, Vec<SubMsg>), ContractError> {
+    if CONTRACT_STATUS.load(deps.storage)? != ContractStatus::Operational {
+        return Err(ContractError::Frozen {});
+    }
+
+    let config = SWAPS.load(deps.storage, id.to_string())?;
+
+    if config.ibc_channel.as_deref() != Some(channel_id) {
+        return Err(ContractError::UnauthorizedIbcChannel {});
+    }
+
+    if to_bob {
+        let secret_hash = hash_secret(&config.hash_algo, &secret);
+        if secret_hash != config.hashlock {
+            return Err(ContractError::InvalidSecret {});
+        }
+    }
+
+    if !to_bob && env.block.time.seconds() < config.timelock {
+        return Err(ContractError::TimelockNotExpired {});
+    }
+
+    let recipient = if to_bob { &config.bob } else { &config.alice };
+
+    let (messages, cw20_withdrawals, coins) =
+        build_withdrawal_messages(deps.branch(), id, recipient)?;
+
+    let action = if to_bob {
+        HtclTxAction::BobWithdraw
+    } else {
+        HtclTxAction::AliceWithdraw
+    };
+    record_tx(
+        deps,
+        env,
+        id.to_string(),
+        action,
+        recipient.to_string(),
+        to_bob.then(|| secret.clone()),
+        coins,
+        cw20_withdrawals,
+    )?;
+
+    if to_bob {
+        Ok(("ibc_reveal_secret".to_string(), messages))
+    } else {
+        Ok(("ibc_claim_refund".to_string(), messages))
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketAckMsg,
+) -> StdResult<IbcBasicResponse> {
+    Ok(IbcBasicResponse::new().add_attribute("method", "ibc_packet_ack"))
+}
+
+// The outgoing `RevealSecret` packet timed out before the counterpart chain
+// acknowledged it; fall back to the same Alice-refund path a local timelock
+// expiry would have triggered, for the swap the packet was about.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> StdResult<IbcBasicResponse> {
+    let payload: IbcPacketPayload = from_json(&msg.packet.data)?;
+    let id = match payload {
+        IbcPacketPayload::RevealSecret { id, .. } => id,
+        IbcPacketPayload::ClaimRefund { id } => id,
+    };
+
+    let config = SWAPS.load(deps.storage, id.clone())?;
+    if env.block.time.seconds() < config.timelock {
+        return Ok(IbcBasicResponse::new()
+            .add_attribute("method", "ibc_packet_timeout")
+            .add_attribute("id", id)
+            .add_attribute("refunded", "false"));
+    }
+
+    let (_, messages) =
+        release_to(deps, &env, &msg.packet.src.channel_id, &id, String::new(), false)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(IbcBasicResponse::new()
+        .add_submessages(messages)
+        .add_attribute("method", "ibc_packet_timeout")
+        .add_attribute("id", id)
+        .add_attribute("refunded", "true"))
+}
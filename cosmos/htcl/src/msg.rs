@@ -1,41 +1,137 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin, Uint128};
+use cosmwasm_std::{Addr, Binary, Coin, Uint128};
 use cw20::Cw20ReceiveMsg;
 
+use crate::state::{ContractStatus, HashAlgo, HtclTx};
+
+// The registry itself holds no swap setup; individual swaps are opened via
+// `ExecuteMsg::Create` so one contract can host many concurrent swaps.
+// `prng_seed` only seeds viewing-key derivation; `admin`, if set, is the only
+// address that can freeze the contract or transfer the admin role.
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub bob: String,
-    pub timelock: u64,
-    pub hashlock: String, // Universal hashlock that works across all chains
-    pub cw20: Option<Addr>,
-    pub native: Option<String>,
+    pub prng_seed: Binary,
+    pub admin: Option<String>,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
+    // Open a new swap under a caller-chosen id
+    Create {
+        id: String,
+        bob: String,
+        timelock: u64,
+        hashlock: String, // Universal hashlock that works across all chains
+        hash_algo: HashAlgo,
+        cw20: Option<Addr>,
+        native: Option<String>,
+        wormhole_bridge: Option<String>,
+        emitter_chain: Option<u16>,
+        emitter_address: Option<Binary>,
+        guardian_addresses: Option<Vec<Binary>>,
+        // IBC channel to the counterpart HTLC on another Cosmos chain; once
+        // set, `BobWithdraw` notifies it with the revealed secret
+        ibc_channel: Option<String>,
+    },
     // Bob can withdraw before timelock with correct secret
-    BobWithdraw { secret: String },
+    BobWithdraw { id: String, secret: String },
     // Alice can withdraw after timelock expires
-    AliceWithdraw {},
-    // Receive cw20 tokens
+    AliceWithdraw { id: String },
+    // Receive cw20 tokens; `msg` must decode to `ReceiveMsg::TopUp { id }`
     Receive(Cw20ReceiveMsg),
     // Receive native tokens
-    DepositNative {},
+    DepositNative { id: String },
+    // Complete the swap using a Wormhole VAA that proves the secret was
+    // revealed on the counterpart chain
+    CompleteFromVaa { id: String, vaa: Binary },
+    // Generate and store a fresh viewing key for the caller, mixing in
+    // `entropy` and the contract's `prng_seed`
+    CreateViewingKey { entropy: String },
+    // Store a caller-chosen viewing key instead of generating one
+    SetViewingKey { key: String },
+    // Admin-only: transfer the admin role, or renounce it with `None`
+    ChangeAdmin { new_admin: Option<String> },
+    // Admin-only: freeze/unfreeze the whole contract
+    SetContractStatus { level: ContractStatus },
+    // Admin-only, and only while `ContractStatus::StopAll`: return a swap's
+    // escrowed native + cw20 funds to Alice regardless of the timelock
+    EmergencyRefund { id: String },
+}
+
+// Payload carried in `Cw20ReceiveMsg.msg` to route a cw20 deposit to a swap
+#[cw_serde]
+pub enum ReceiveMsg {
+    TopUp { id: String },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(ConfigResponse)]
-    GetConfig {},
+    // Non-sensitive: reveals neither the hashlock nor anything useful for
+    // brute-forcing the secret
     #[returns(BalanceResponse)]
-    GetBalance {},
-    #[returns(bool)]
-    IsTimelockExpired {},
+    GetBalance { id: String },
     #[returns(bool)]
-    IsValidSecret { secret: String },
-    #[returns(ContractInfoResponse)]
-    GetContractInfo {},
+    IsTimelockExpired { id: String },
+    #[returns(ListSwapsResponse)]
+    ListSwaps {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Reverse-chronological, contract-wide transaction log with a total count
+    #[returns(TransactionHistoryResponse)]
+    TransactionHistory { page: u32, page_size: u32 },
+    // Sensitive queries (hashlock reads, secret checks) only answer once the
+    // caller proves they are Alice or Bob on the target swap
+    #[returns(Binary)]
+    WithPermit {
+        permit: Permit,
+        query: AuthenticatedQueryMsg,
+    },
+    #[returns(Binary)]
+    WithViewingKey {
+        address: String,
+        viewing_key: String,
+        query: AuthenticatedQueryMsg,
+    },
+}
+
+// Queries that leak the hashlock or let a caller check secret guesses;
+// gated behind `QueryMsg::WithPermit`/`QueryMsg::WithViewingKey`
+#[cw_serde]
+pub enum AuthenticatedQueryMsg {
+    Details { id: String },
+    IsValidSecret { id: String, secret: String },
+    GetContractInfo { id: String },
+}
+
+// A signed credential (with no expiry) proving the signer controls `pub_key`,
+// scoped to the query kinds named in `allowed_queries` (e.g. "is_valid_secret")
+#[cw_serde]
+pub struct Permit {
+    pub params: PermitParams,
+    pub signature: PermitSignature,
+}
+
+#[cw_serde]
+pub struct PermitParams {
+    pub permit_name: String,
+    pub chain_id: String,
+    pub allowed_queries: Vec<String>,
+}
+
+#[cw_serde]
+pub struct PermitSignature {
+    pub pub_key: Binary,
+    pub signature: Binary,
+}
+
+#[cw_serde]
+pub struct CreateViewingKeyResponse {
+    pub key: String,
 }
 
 #[cw_serde]
@@ -44,6 +140,9 @@ pub struct ConfigResponse {
     pub bob: String,
     pub timelock: u64,
     pub hashlock: String, // Universal hashlock
+    pub hash_algo: HashAlgo,
+    pub cw20: Option<Addr>,
+    pub native: Option<String>,
 }
 
 #[cw_serde]
@@ -64,6 +163,20 @@ pub struct ContractInfoResponse {
     pub bob: String,
     pub timelock: u64,
     pub hashlock: String, // Universal hashlock
+    pub hash_algo: HashAlgo,
+    pub cw20: Option<Addr>,
+    pub native: Option<String>,
     pub native_balance: Vec<Coin>,
     pub cw20_balances: Vec<Cw20Balance>,
 }
+
+#[cw_serde]
+pub struct ListSwapsResponse {
+    pub swaps: Vec<String>,
+}
+
+#[cw_serde]
+pub struct TransactionHistoryResponse {
+    pub txs: Vec<HtclTx>,
+    pub total: u64,
+}
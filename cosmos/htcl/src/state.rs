@@ -1,20 +1,101 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Uint128};
+use cosmwasm_std::{Addr, Binary, Coin, Uint128};
 use cw_storage_plus::{Item, Map};
 
+// Hashing convention used to validate the revealed secret against
+// `Config.hashlock`. EVM-compatible counterpart chains commit to
+// `keccak256(secret)` rather than `sha256(secret)`.
+#[cw_serde]
+pub enum HashAlgo {
+    Sha256,
+    Keccak256,
+}
+
 #[cw_serde]
 pub struct Config {
     pub alice: Addr,
     pub bob: Addr,
     pub timelock: u64,
     pub hashlock: String, // Universal hashlock that works across all chains
+    pub hash_algo: HashAlgo,
     pub cw20: Option<Addr>,
     pub native: Option<String>,
+    // Wormhole core-bridge contract that secret reveals are published to
+    pub wormhole_bridge: Option<Addr>,
+    // Chain id of the counterpart HTLC allowed to complete this swap via VAA
+    pub emitter_chain: Option<u16>,
+    // Emitter address (32 bytes) of the counterpart HTLC on `emitter_chain`
+    pub emitter_address: Option<Binary>,
+    // Guardian set: 20-byte guardian addresses used to verify VAA signatures
+    pub guardian_addresses: Option<Vec<Binary>>,
+    // IBC channel to the counterpart HTLC instance on another Cosmos chain
+    pub ibc_channel: Option<String>,
+}
+
+// Swaps, keyed by a caller-chosen id so one contract can host many
+// concurrent atomic swaps instead of one per instance
+pub const SWAPS: Map<String, Config> = Map::new("swaps");
+pub const CW20_BALANCES: Map<(String, Addr), Uint128> = Map::new("cw20_balances");
+pub const NATIVE_BALANCES: Map<(String, String), Uint128> = Map::new("native_balances");
+// Replay protection: consumed VAAs, keyed by "{emitter_chain}:{emitter_address}:{sequence}"
+pub const CONSUMED_VAAS: Map<String, bool> = Map::new("consumed_vaas");
+
+// Seed mixed into every viewing key generated by `CreateViewingKey` so those
+// keys can't be predicted without it; `SetViewingKey` keys don't use it
+pub const PRNG_SEED: Item<Binary> = Item::new("prng_seed");
+// SNIP-20-style viewing keys: only the sha256 of the key is ever stored
+pub const VIEWING_KEYS: Map<Addr, [u8; 32]> = Map::new("viewing_keys");
+
+// Contract-wide admin, independent of any one swap's alice/bob; set at
+// instantiate (or to `None` by `migrate` on contracts that predate it) and
+// transferable via `ExecuteMsg::ChangeAdmin`
+pub const ADMIN: Item<Option<Addr>> = Item::new("admin");
+
+// Killswitch levels, in order of escalation
+#[cw_serde]
+pub enum ContractStatus {
+    // Everything works as normal
+    Operational,
+    // New swaps and fund movement are frozen; no emergency refunds yet
+    StopTransactions,
+    // Frozen, plus the admin may emergency-refund a swap's escrowed funds
+    StopAll,
+}
+
+pub const CONTRACT_STATUS: Item<ContractStatus> = Item::new("contract_status");
+
+// On-chain, append-only transaction log so UIs/indexers/relayers can
+// reconstruct a swap's lifecycle (including the revealed secret a relayer
+// needs to claim the counterpart escrow) without scraping block events
+#[cw_serde]
+pub enum HtclTxAction {
+    Create,
+    BobWithdraw,
+    AliceWithdraw,
 }
 
-pub const CONFIG: Item<Config> = Item::new("config");
-pub const CW20_BALANCES: Map<Addr, Uint128> = Map::new("cw20_balances");
-pub const NATIVE_BALANCES: Map<String, Uint128> = Map::new("native_balances");
+#[cw_serde]
+pub struct HtclTx {
+    pub swap_id: String,
+    pub action: HtclTxAction,
+    pub height: u64,
+    pub timestamp: u64,
+    pub counterparty: String,
+    pub secret: Option<String>,
+    pub native_amount: Vec<Coin>,
+    pub cw20_amount: Vec<Cw20Withdrawal>,
+}
+
+pub const TX_HISTORY: Map<u64, HtclTx> = Map::new("tx_history");
+pub const TX_COUNT: Item<u64> = Item::new("tx_count");
+
+// Payload published to / read back from the Wormhole core bridge
+#[cw_serde]
+pub struct WormholeSecretPayload {
+    pub hashlock: String,
+    pub secret: String,
+    pub swap_recipient: String,
+}
 
 // Events
 #[cw_serde]